@@ -1,6 +1,15 @@
-use std::{collections::VecDeque, time::Duration};
+use std::{
+    collections::VecDeque,
+    io::IsTerminal,
+    sync::{Arc, Mutex},
+    time::Duration,
+    time::Instant,
+};
 
-use indicatif::{style::ProgressTracker, HumanBytes, ProgressBar, ProgressStyle};
+use indicatif::{
+    style::ProgressTracker, HumanBytes, MultiProgress, ProgressBar, ProgressDrawTarget,
+    ProgressStyle,
+};
 use serde::Deserialize;
 use unicode_width::UnicodeWidthChar;
 
@@ -33,42 +42,163 @@ impl Progress for () {
     fn finish(&mut self) {}
 }
 
-impl Progress for ProgressBar {
+/// Wraps a [`Progress`] implementor to coalesce `update` calls, forwarding
+/// the first one immediately and then suppressing further forwards until
+/// at least `interval` has elapsed, accumulating the intervening byte
+/// counts and flushing the accumulated delta on the next allowed tick (and
+/// in [`finish`](Progress::finish)).
+///
+/// This is modeled on cargo's own `Throttle` and exists so that expensive
+/// custom implementations (logging, IPC, GUI updates) aren't invoked for
+/// every received chunk on a fast link.
+pub struct Throttle<P: Progress> {
+    inner: P,
+    interval: Duration,
+    last_forward: Option<Instant>,
+    pending: usize,
+}
+
+impl<P: Progress> Throttle<P> {
+    /// Wraps `inner`, forwarding at most one `update` per `interval`.
+    /// The [`Default`] impl uses an interval of 100ms.
+    pub fn new(inner: P, interval: Duration) -> Self {
+        Self {
+            inner,
+            interval,
+            last_forward: None,
+            pending: 0,
+        }
+    }
+}
+
+impl<P: Progress> Default for Throttle<P>
+where
+    P: Default,
+{
+    fn default() -> Self {
+        Self::new(P::default(), Duration::from_millis(100))
+    }
+}
+
+impl<P: Progress> Progress for Throttle<P> {
     fn init(&mut self, size: usize, filename: &str) {
-        self.set_length(size as u64);
-        self.set_style(
-                ProgressStyle::with_template(
-                    "{msg} [{elapsed_precise}] [{wide_bar}] {bytes}/{total_bytes} {bytes_per_sec_smoothed} ({eta})",
-                ).unwrap().with_key("bytes_per_sec_smoothed", MovingAvgRate::default())
-                    ,
-            );
-        let maxlength = 30;
-        // This width is display length, not byte length, CJK fonts in terminal always width=2
-        let display_width: usize = filename.chars().map(|c| c.width().unwrap_or(0)).sum();
-
-        let message = if display_width <= maxlength {
-            filename.to_string()
-        } else {
-            let target_width = maxlength - 2; // Change width to 28 for the ".." prefix
-            let mut current_width = 0;
-            let mut start_index = filename.len();
+        self.last_forward = None;
+        self.pending = 0;
+        self.inner.init(size, filename);
+    }
 
-            for (i, c) in filename.char_indices().rev() {
-                let char_width = c.width().unwrap_or(0);
+    fn update(&mut self, size: usize) {
+        self.pending += size;
+        let due = self
+            .last_forward
+            .is_none_or(|last| last.elapsed() >= self.interval);
+        if due {
+            let pending = std::mem::take(&mut self.pending);
+            self.inner.update(pending);
+            self.last_forward = Some(Instant::now());
+        }
+    }
 
-                // if add this character exceeds target width, stop
-                if current_width + char_width > target_width {
-                    break;
-                }
+    fn finish(&mut self) {
+        if self.pending > 0 {
+            let pending = std::mem::take(&mut self.pending);
+            self.inner.update(pending);
+        }
+        self.inner.finish();
+    }
+}
+
+/// Controls whether downloads render a progress bar.
+///
+/// Defaults to [`ProgressDisplay::Auto`], which follows the same heuristic
+/// cargo uses for its own progress output: the bar is suppressed when
+/// `CI` is set, when `TERM=dumb`, or when stderr isn't a terminal.
+/// Callers that want to force the bar on or off regardless of environment
+/// can pick `Always` or `Never` instead.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum ProgressDisplay {
+    /// Show the bar only when running interactively.
+    #[default]
+    Auto,
+    /// Always show the bar, regardless of environment.
+    Always,
+    /// Never show the bar.
+    Never,
+}
+
+impl ProgressDisplay {
+    /// Resolves this setting against the current environment, returning
+    /// whether a progress bar should actually be drawn.
+    pub fn enabled(self) -> bool {
+        match self {
+            ProgressDisplay::Always => true,
+            ProgressDisplay::Never => false,
+            ProgressDisplay::Auto => is_interactive(),
+        }
+    }
+}
+
+fn is_interactive() -> bool {
+    if std::env::var_os("CI").is_some() {
+        return false;
+    }
+    if std::env::var("TERM").is_ok_and(|term| term == "dumb") {
+        return false;
+    }
+    std::io::stderr().is_terminal()
+}
+
+/// Applies the shared style/message setup for a single-file bar. Split out
+/// of `impl Progress for ProgressBar` so [`ConfiguredProgressBar`] can reuse
+/// it without going through that impl's own environment auto-check.
+fn style_file_bar(bar: &ProgressBar, size: usize, filename: &str) {
+    bar.set_length(size as u64);
+    bar.set_style(
+        ProgressStyle::with_template(
+            "{msg} [{elapsed_precise}] [{wide_bar}] {bytes}/{total_bytes} {bytes_per_sec_smoothed} ({eta_smoothed})",
+        ).unwrap().with_key("bytes_per_sec_smoothed", MovingAvgRate::default())
+            .with_key("eta_smoothed", MovingAvgEta::default())
+            ,
+    );
+    let maxlength = 30;
+    // This width is display length, not byte length, CJK fonts in terminal always width=2
+    let display_width: usize = filename.chars().map(|c| c.width().unwrap_or(0)).sum();
+
+    let message = if display_width <= maxlength {
+        filename.to_string()
+    } else {
+        let target_width = maxlength - 2; // Change width to 28 for the ".." prefix
+        let mut current_width = 0;
+        let mut start_index = filename.len();
+
+        for (i, c) in filename.char_indices().rev() {
+            let char_width = c.width().unwrap_or(0);
 
-                current_width += char_width;
-                start_index = i;
+            // if add this character exceeds target width, stop
+            if current_width + char_width > target_width {
+                break;
             }
 
-            format!("..{}", &filename[start_index..])
-        };
+            current_width += char_width;
+            start_index = i;
+        }
+
+        format!("..{}", &filename[start_index..])
+    };
 
-        self.set_message(message);
+    bar.set_message(message);
+}
+
+impl Progress for ProgressBar {
+    fn init(&mut self, size: usize, filename: &str) {
+        // Apply the auto heuristic, hiding a visible bar when the environment
+        // isn't interactive; it never un-hides a bar. Bars made with an
+        // explicit choice via `new_progress_bar` are `ConfiguredProgressBar`s,
+        // which skip this check.
+        if !self.is_hidden() && !ProgressDisplay::Auto.enabled() {
+            self.set_draw_target(ProgressDrawTarget::hidden());
+        }
+        style_file_bar(self, size, filename);
     }
 
     fn update(&mut self, size: usize) {
@@ -80,6 +210,284 @@ impl Progress for ProgressBar {
     }
 }
 
+/// A [`ProgressBar`] paired with an explicit [`ProgressDisplay`] choice.
+///
+/// Plain `ProgressBar`'s `Progress::init` re-derives visibility from the
+/// environment every time, which would hide a bar the caller forced on with
+/// `Always` in a non-interactive environment. This instead remembers the
+/// caller's setting and resolves it in every `init`, so a forced choice
+/// sticks regardless of environment.
+pub struct ConfiguredProgressBar {
+    bar: ProgressBar,
+    display: ProgressDisplay,
+}
+
+impl ConfiguredProgressBar {
+    /// The underlying bar, e.g. to add it to a [`MultiProgress`].
+    pub fn bar(&self) -> &ProgressBar {
+        &self.bar
+    }
+}
+
+impl Progress for ConfiguredProgressBar {
+    fn init(&mut self, size: usize, filename: &str) {
+        if !self.display.enabled() {
+            self.bar.set_draw_target(ProgressDrawTarget::hidden());
+        }
+        style_file_bar(&self.bar, size, filename);
+    }
+
+    fn update(&mut self, size: usize) {
+        self.bar.inc(size as u64)
+    }
+
+    fn finish(&mut self) {
+        self.bar.finish();
+    }
+}
+
+/// Creates a [`ConfiguredProgressBar`] honoring the given [`ProgressDisplay`]
+/// setting, for callers (e.g. a download builder on the `tokio`/`sync` API
+/// types) that want to force progress on or off instead of relying on the
+/// environment auto-detection in [`Progress for ProgressBar`](Progress).
+pub fn new_progress_bar(display: ProgressDisplay, len: u64) -> ConfiguredProgressBar {
+    let bar = ProgressBar::new(len);
+    if !display.enabled() {
+        bar.set_draw_target(ProgressDrawTarget::hidden());
+    }
+    ConfiguredProgressBar { bar, display }
+}
+
+/// An overflowed file still waiting for a bar slot to free up, queued so a
+/// finishing bar can promote it instead of leaving it stuck behind the
+/// "+N more" line until its own next `update`.
+struct PendingFile {
+    slot: Arc<Mutex<Option<ProgressBar>>>,
+    size: usize,
+    filename: String,
+    done: Arc<Mutex<bool>>,
+}
+
+/// Shared state backing every per-file bar spawned by a [`MultiBarProgress`].
+struct MultiBarState {
+    multi: MultiProgress,
+    active: usize,
+    overflow: usize,
+    overflow_bar: Option<ProgressBar>,
+    pending: VecDeque<PendingFile>,
+}
+
+impl MultiBarState {
+    /// Promotes the oldest still-waiting overflowed file into a real bar,
+    /// if any. Called whenever a bar finishes and frees up a slot.
+    fn drain_pending(&mut self) {
+        while let Some(pending) = self.pending.pop_front() {
+            if *pending.done.lock().unwrap() {
+                // The file finished while still overflowed; nothing to promote.
+                continue;
+            }
+            self.overflow -= 1;
+            self.active += 1;
+            let mut bar = self
+                .multi
+                .insert_from_back(1, ProgressBar::new(pending.size as u64));
+            Progress::init(&mut bar, pending.size, &pending.filename);
+            *pending.slot.lock().unwrap() = Some(bar);
+            break;
+        }
+    }
+}
+
+impl MultiBarState {
+    fn overflow_line(&mut self) {
+        if self.overflow == 0 {
+            if let Some(bar) = self.overflow_bar.take() {
+                bar.finish_and_clear();
+            }
+            return;
+        }
+        let message = format!("  +{} more", self.overflow);
+        match &self.overflow_bar {
+            Some(bar) => bar.set_message(message),
+            None => {
+                let bar = self.multi.add(ProgressBar::new(0));
+                bar.set_style(ProgressStyle::with_template("{msg}").unwrap());
+                bar.set_message(message);
+                self.overflow_bar = Some(bar);
+            }
+        }
+    }
+}
+
+/// Coordinates progress across every file in a multi-file download (e.g. a
+/// whole snapshot), rendering one overall bar tracking total bytes across
+/// all siblings plus up to `max_bar_count` per-file bars. Files in flight
+/// beyond that cap are collapsed into a single "+N more" line instead of
+/// flooding the terminal, and a newly started file only gets its own bar
+/// once `delay` has elapsed, so fast files never flicker one into view.
+#[derive(Clone)]
+pub struct MultiBarProgress {
+    overall: ProgressBar,
+    max_bar_count: usize,
+    delay: Duration,
+    state: Arc<Mutex<MultiBarState>>,
+}
+
+impl MultiBarProgress {
+    /// Creates a new coordinator for a download of `total_size` bytes spread
+    /// across an arbitrary number of files.
+    pub fn new(total_size: u64, max_bar_count: usize, delay: Duration) -> Self {
+        let multi = MultiProgress::new();
+        let overall = multi.add(ProgressBar::new(total_size));
+        overall.set_style(
+            ProgressStyle::with_template(
+                "{msg} [{elapsed_precise}] [{wide_bar}] {bytes}/{total_bytes} {bytes_per_sec_smoothed} ({eta_smoothed})",
+            )
+            .unwrap()
+            .with_key("bytes_per_sec_smoothed", MovingAvgRate::default())
+            .with_key("eta_smoothed", MovingAvgEta::default()),
+        );
+        overall.set_message("Total");
+        Self {
+            overall,
+            max_bar_count,
+            delay,
+            state: Arc::new(Mutex::new(MultiBarState {
+                multi,
+                active: 0,
+                overflow: 0,
+                overflow_bar: None,
+                pending: VecDeque::new(),
+            })),
+        }
+    }
+
+    /// Returns a fresh [`Progress`] handle for a single sibling; callers
+    /// should create one per file and drive it the same way they would a
+    /// plain [`ProgressBar`].
+    pub fn file_progress(&self) -> MultiFileProgress {
+        MultiFileProgress {
+            parent: self.clone(),
+            bar: Arc::new(Mutex::new(None)),
+            started_at: None,
+            size: 0,
+            filename: String::new(),
+            overflowed: false,
+            done: Arc::new(Mutex::new(false)),
+        }
+    }
+
+    /// Finishes and clears the overall bar, along with any lingering
+    /// overflow line. Call this once every file has finished.
+    pub fn finish(&self) {
+        self.overall.finish_and_clear();
+        let mut state = self.state.lock().unwrap();
+        state.overflow = 0;
+        state.pending.clear();
+        state.overflow_line();
+    }
+}
+
+/// A single file's handle into a [`MultiBarProgress`]. Implements [`Progress`]
+/// so it can be used anywhere a single-file progress reporter is expected.
+///
+/// `bar` is shared (rather than owned outright) so that a file queued behind
+/// the `max_bar_count` cap can be promoted into a real bar by some other
+/// file's [`finish`](Progress::finish) as soon as a slot frees up, not just
+/// on this file's own next `update`.
+pub struct MultiFileProgress {
+    parent: MultiBarProgress,
+    bar: Arc<Mutex<Option<ProgressBar>>>,
+    started_at: Option<Instant>,
+    size: usize,
+    filename: String,
+    /// Whether this file is currently counted in `state.overflow` /
+    /// `state.pending`. Guards against `update`'s per-chunk retries
+    /// re-incrementing the overflow count on every call.
+    overflowed: bool,
+    done: Arc<Mutex<bool>>,
+}
+
+impl MultiFileProgress {
+    fn try_spawn_bar(&mut self) {
+        if self.bar.lock().unwrap().is_some() {
+            return;
+        }
+        let Some(started_at) = self.started_at else {
+            return;
+        };
+        if started_at.elapsed() < self.parent.delay {
+            return;
+        }
+        let mut state = self.parent.state.lock().unwrap();
+        if state.active >= self.parent.max_bar_count {
+            if !self.overflowed {
+                self.overflowed = true;
+                state.overflow += 1;
+                state.pending.push_back(PendingFile {
+                    slot: self.bar.clone(),
+                    size: self.size,
+                    filename: self.filename.clone(),
+                    done: self.done.clone(),
+                });
+                state.overflow_line();
+            }
+            return;
+        }
+        if self.overflowed {
+            self.overflowed = false;
+            state.overflow -= 1;
+        }
+        let mut bar = state
+            .multi
+            .insert_from_back(1, ProgressBar::new(self.size as u64));
+        state.active += 1;
+        state.overflow_line();
+        drop(state);
+        Progress::init(&mut bar, self.size, &self.filename);
+        *self.bar.lock().unwrap() = Some(bar);
+    }
+}
+
+impl Progress for MultiFileProgress {
+    fn init(&mut self, size: usize, filename: &str) {
+        self.size = size;
+        self.filename = filename.to_string();
+        self.started_at = Some(Instant::now());
+        self.try_spawn_bar();
+    }
+
+    fn update(&mut self, size: usize) {
+        self.try_spawn_bar();
+        // A slot may have opened and promoted this file via `drain_pending`
+        // since the last call, so re-check the shared slot rather than a
+        // locally cached `Option<ProgressBar>`.
+        if let Some(bar) = self.bar.lock().unwrap().as_mut() {
+            Progress::update(bar, size);
+        }
+        self.parent.overall.inc(size as u64);
+    }
+
+    fn finish(&mut self) {
+        *self.done.lock().unwrap() = true;
+        let mut state = self.parent.state.lock().unwrap();
+        match self.bar.lock().unwrap().take() {
+            Some(mut bar) => {
+                Progress::finish(&mut bar);
+                state.active -= 1;
+                state.drain_pending();
+            }
+            None => {
+                if self.overflowed {
+                    self.overflowed = false;
+                    state.overflow = state.overflow.saturating_sub(1);
+                }
+            }
+        }
+        state.overflow_line();
+    }
+}
+
 /// Siblings are simplified file descriptions of remote files on the hub
 #[derive(Debug, Clone, Deserialize, PartialEq)]
 pub struct Siblings {
@@ -97,6 +505,42 @@ pub struct RepoInfo {
     pub sha: String,
 }
 
+/// Pushes a new `(now, pos)` sample and drops samples older than 1 second,
+/// keeping a sliding window shared by [`MovingAvgRate`] and [`MovingAvgEta`].
+fn slide_sample_window(samples: &mut VecDeque<(std::time::Instant, u64)>, now: std::time::Instant, pos: u64) {
+    // sample at most every 20ms
+    if samples
+        .back()
+        .is_none_or(|(prev, _)| (now - *prev) > Duration::from_millis(20))
+    {
+        samples.push_back((now, pos));
+    }
+
+    while let Some(first) = samples.front() {
+        if now - first.0 > Duration::from_secs(1) {
+            samples.pop_front();
+        } else {
+            break;
+        }
+    }
+}
+
+/// Bytes/sec over the retained window, or `None` if there aren't enough
+/// samples yet (or the window spans zero time).
+fn windowed_rate(samples: &VecDeque<(std::time::Instant, u64)>) -> Option<f64> {
+    let (Some((t0, p0)), Some((t1, p1))) = (samples.front(), samples.back()) else {
+        return None;
+    };
+    if samples.len() <= 1 {
+        return None;
+    }
+    let elapsed_ms = (*t1 - *t0).as_millis();
+    if elapsed_ms == 0 {
+        return None;
+    }
+    Some((p1 - p0) as f64 * 1000f64 / elapsed_ms as f64)
+}
+
 #[derive(Clone, Default)]
 struct MovingAvgRate {
     samples: VecDeque<(std::time::Instant, u64)>,
@@ -108,22 +552,7 @@ impl ProgressTracker for MovingAvgRate {
     }
 
     fn tick(&mut self, state: &indicatif::ProgressState, now: std::time::Instant) {
-        // sample at most every 20ms
-        if self
-            .samples
-            .back()
-            .is_none_or(|(prev, _)| (now - *prev) > Duration::from_millis(20))
-        {
-            self.samples.push_back((now, state.pos()));
-        }
-
-        while let Some(first) = self.samples.front() {
-            if now - first.0 > Duration::from_secs(1) {
-                self.samples.pop_front();
-            } else {
-                break;
-            }
-        }
+        slide_sample_window(&mut self.samples, now, state.pos());
     }
 
     fn reset(&mut self, _state: &indicatif::ProgressState, _now: std::time::Instant) {
@@ -131,11 +560,44 @@ impl ProgressTracker for MovingAvgRate {
     }
 
     fn write(&self, _state: &indicatif::ProgressState, w: &mut dyn std::fmt::Write) {
-        match (self.samples.front(), self.samples.back()) {
-            (Some((t0, p0)), Some((t1, p1))) if self.samples.len() > 1 => {
-                let elapsed_ms = (*t1 - *t0).as_millis();
-                let rate = ((p1 - p0) as f64 * 1000f64 / elapsed_ms as f64) as u64;
-                write!(w, "{}/s", HumanBytes(rate)).unwrap()
+        match windowed_rate(&self.samples) {
+            Some(rate) => write!(w, "{}/s", HumanBytes(rate as u64)).unwrap(),
+            None => write!(w, "-").unwrap(),
+        }
+    }
+}
+
+/// A smoothed ETA, rendered under the `{eta_smoothed}` template key.
+///
+/// Unlike indicatif's built-in `{eta}`, which is derived from the whole
+/// download's average rate and jumps around on variable connections, this
+/// reuses the same 1-second sliding window as [`MovingAvgRate`] and divides
+/// the remaining bytes by that windowed rate, so the displayed ETA tracks
+/// the displayed `{bytes_per_sec_smoothed}` rate.
+#[derive(Clone, Default)]
+struct MovingAvgEta {
+    samples: VecDeque<(std::time::Instant, u64)>,
+}
+
+impl ProgressTracker for MovingAvgEta {
+    fn clone_box(&self) -> Box<dyn ProgressTracker> {
+        Box::new(self.clone())
+    }
+
+    fn tick(&mut self, state: &indicatif::ProgressState, now: std::time::Instant) {
+        slide_sample_window(&mut self.samples, now, state.pos());
+    }
+
+    fn reset(&mut self, _state: &indicatif::ProgressState, _now: std::time::Instant) {
+        self.samples = Default::default();
+    }
+
+    fn write(&self, state: &indicatif::ProgressState, w: &mut dyn std::fmt::Write) {
+        match windowed_rate(&self.samples) {
+            Some(rate) if rate > 0.0 => {
+                let remaining = state.len().unwrap_or(0).saturating_sub(state.pos()) as f64;
+                let eta = Duration::from_secs_f64(remaining / rate);
+                write!(w, "{}", indicatif::FormattedDuration(eta)).unwrap()
             }
             _ => write!(w, "-").unwrap(),
         }